@@ -0,0 +1,98 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::BigInt;
+
+/// A commutative ring, generic enough to let `OddMonomial`/`OddPolynomial`
+/// and their operators be written without committing to `i32`, `BigInt`, or
+/// `ModInt` specifically.
+pub trait Coefficient
+    : Clone + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+    {
+    /// The additive identity.
+    fn zero() -> Self;
+    /// The multiplicative identity.
+    fn one() -> Self;
+    /// Whether this value is the additive identity.
+    fn is_zero(&self) -> bool;
+    /// Whether this value should be displayed with a leading minus sign.
+    fn is_negative(&self) -> bool;
+    /// Constructs the ring element corresponding to the signed integer `n`,
+    /// used to interpret coefficients written out as plain integer literals.
+    fn from_int(n: i32) -> Self;
+}
+
+/// A coefficient ring in which every nonzero element has a multiplicative
+/// inverse.
+///
+/// This is required to divide by a generator's leading coefficient when it
+/// is not `1` or `-1`, as happens when reducing a polynomial modulo an
+/// ideal (see `OddPolynomial::reduce`). `i32` and `BigInt` are not fields,
+/// so they do not implement this trait; `ModInt` does.
+pub trait InvertibleCoefficient: Coefficient {
+    /// The multiplicative inverse of a nonzero element.
+    fn inverse(&self) -> Self;
+}
+
+impl Coefficient for i32 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < 0
+    }
+
+    fn from_int(n: i32) -> Self {
+        n
+    }
+}
+
+impl Coefficient for BigInt {
+    fn zero() -> Self {
+        BigInt::from(0)
+    }
+
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == BigInt::from(0)
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < BigInt::from(0)
+    }
+
+    fn from_int(n: i32) -> Self {
+        BigInt::from(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn i32_from_int_round_trips() {
+        assert_eq!(i32::from_int(-7), -7);
+        assert!(i32::from_int(0).is_zero());
+        assert!(Coefficient::is_negative(&-3i32));
+    }
+
+    #[test]
+    fn big_int_from_int_matches_i32_arithmetic() {
+        let a = BigInt::from_int(3);
+        let b = BigInt::from_int(4);
+        assert_eq!(a + b, BigInt::from_int(7));
+        assert!(BigInt::from_int(-1).is_negative());
+    }
+}
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate error_chain;
 
+extern crate num_bigint;
+
 pub mod errors {
     error_chain!{
         errors {
@@ -13,8 +15,13 @@ pub mod errors {
     }
 }
 
+mod coefficient;
+mod mod_int;
+mod parser;
 mod polynomial;
 
+pub use coefficient::Coefficient;
+pub use mod_int::{ModInt, modulus, set_modulus};
 pub use polynomial::{OddPolynomial, OddMonomial};
 
 #[cfg(test)]
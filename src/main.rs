@@ -2,12 +2,15 @@
 extern crate error_chain;
 
 extern crate nilhecke;
+extern crate num_bigint;
 
 use std::collections::HashSet;
 use std::io::{self, Write};
 
+use num_bigint::BigInt;
+
 use nilhecke::errors::*;
-use nilhecke::{OddMonomial, OddPolynomial};
+use nilhecke::{Coefficient, ModInt, OddMonomial, OddPolynomial};
 
 const VERSION: &str = "0.1.0";
 
@@ -35,14 +38,43 @@ fn main() {
 }
 
 fn run() -> Result<()> {
+    // When set, `p` and `schud` work over `F_p` via `ModInt` instead of `i32`.
+    let mut modulus: Option<u32> = None;
+    // When enabled, `p` and `schud` work over arbitrary-precision integers
+    // via `BigInt` instead of `i32`, taking priority over `modulus`.
+    let mut exact = false;
+
     loop {
         println!();
         match prompt("function:").as_str() {
             "print" => print()?,
             "add" => add()?,
             "mul" => mul()?,
-            "p" => p()?,
-            "schud" => schud()?,
+            "modulus" => modulus = Some(set_modulus()?),
+            "exact" => {
+                exact = !exact;
+                println!("exact arithmetic is now {}", if exact { "on" } else { "off" });
+            }
+            "p" => {
+                if exact {
+                    p_with::<BigInt>(None)?
+                } else {
+                    match modulus {
+                        Some(p) => p_with::<ModInt>(Some(p))?,
+                        None => p_with::<i32>(None)?,
+                    }
+                }
+            }
+            "schud" => {
+                if exact {
+                    schud_with::<BigInt>(None)?
+                } else {
+                    match modulus {
+                        Some(p) => schud_with::<ModInt>(Some(p))?,
+                        None => schud_with::<i32>(None)?,
+                    }
+                }
+            }
             "" | "quit" | "bye" => break,
             _ => println!("unknown function"),
         }
@@ -53,29 +85,44 @@ fn run() -> Result<()> {
 }
 
 fn print() -> Result<()> {
-    println!("{}", prompt("polynomial:").parse::<OddPolynomial>()?);
+    println!("{}", prompt("polynomial:").parse::<OddPolynomial<i32>>()?);
     Ok(())
 }
 
 fn add() -> Result<()> {
-    let p1 = prompt("p1:").parse::<OddPolynomial>()?;
-    let p2 = prompt("p2:").parse::<OddPolynomial>()?;
+    let p1 = prompt("p1:").parse::<OddPolynomial<i32>>()?;
+    let p2 = prompt("p2:").parse::<OddPolynomial<i32>>()?;
     println!("{} + {} = {}", p1, p2, &p1 + &p2);
 
     Ok(())
 }
 
 fn mul() -> Result<()> {
-    let p1 = prompt("p1:").parse::<OddPolynomial>()?;
-    let p2 = prompt("p2:").parse::<OddPolynomial>()?;
+    let p1 = prompt("p1:").parse::<OddPolynomial<i32>>()?;
+    let p2 = prompt("p2:").parse::<OddPolynomial<i32>>()?;
     println!("{} * {} = {}", p1, p2, &p1 * &p2);
 
     Ok(())
 }
 
-fn p() -> Result<()> {
+fn set_modulus() -> Result<u32> {
+    let p = prompt("modulus:")
+        .parse::<u32>()
+        .chain_err(|| "invalid modulus")?;
+    if p < 2 {
+        bail!("modulus must be at least 2");
+    }
+    nilhecke::set_modulus(p);
+    Ok(p)
+}
+
+fn p_with<C: Coefficient + ::std::fmt::Display>(modulus: Option<u32>) -> Result<()> {
+    if let Some(p) = modulus {
+        nilhecke::set_modulus(p);
+    }
+
     let ops = prompt("operators:");
-    let mut poly = prompt("poly:").parse::<OddPolynomial>()?;
+    let mut poly = prompt("poly:").parse::<OddPolynomial<C>>()?;
 
     for op in ops.split_whitespace().rev() {
         let op_num = op[1..]
@@ -112,12 +159,17 @@ fn p() -> Result<()> {
     Ok(())
 }
 
-fn schud() -> Result<()> {
+fn schud_with<C: Coefficient + Eq + ::std::hash::Hash + ::std::fmt::Display>(modulus: Option<u32>)
+                                                                              -> Result<()> {
+    if let Some(p) = modulus {
+        nilhecke::set_modulus(p);
+    }
+
     let n = prompt("n:").parse::<u32>().chain_err(|| "invalid number")?;
     if n < 2 {
         bail!("invalid value for n");
     }
-    let deltad = OddMonomial::deltad(n);
+    let deltad = OddMonomial::<C>::deltad(n);
     let degree = deltad.degree();
     let mut schuberts = HashSet::new();
     schuberts.insert(OddPolynomial::from_monomial(deltad));
@@ -0,0 +1,182 @@
+use std::cell::Cell;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use coefficient::{Coefficient, InvertibleCoefficient};
+
+thread_local! {
+    /// The modulus `p` currently in effect for `ModInt` arithmetic.
+    static MODULUS: Cell<u32> = const { Cell::new(2_147_483_647) };
+}
+
+/// Sets the modulus `p` used by all `ModInt` arithmetic on the current
+/// thread, so the odd nilHecke operators can be run over `F_p`.
+pub fn set_modulus(p: u32) {
+    MODULUS.with(|m| m.set(p));
+}
+
+/// Returns the modulus `p` currently in effect for `ModInt` arithmetic.
+pub fn modulus() -> u32 {
+    MODULUS.with(|m| m.get())
+}
+
+/// An element of the finite field `F_p`, where `p` is the modulus most
+/// recently set with `set_modulus`.
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+pub struct ModInt {
+    value: u32,
+}
+
+impl ModInt {
+    /// Constructs the element of `F_p` congruent to `value` modulo the
+    /// current modulus.
+    pub fn new(value: u32) -> ModInt {
+        ModInt { value: value % modulus() }
+    }
+
+    /// Returns the representative of this element in `0..p`.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Raises this element to the given power by repeated squaring.
+    fn pow(&self, mut exponent: u32) -> ModInt {
+        let mut result = ModInt::new(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = result * base.clone();
+            }
+            base = base.clone() * base;
+            exponent /= 2;
+        }
+        result
+    }
+}
+
+impl Add for ModInt {
+    type Output = ModInt;
+
+    fn add(self, other: ModInt) -> ModInt {
+        // Widen to `u64` like `Mul` does: for `p` near `u32::MAX`, adding two
+        // values just under `p` in `u32` overflows.
+        let p = modulus() as u64;
+        let mut value = self.value as u64 + other.value as u64;
+        if value >= p {
+            value -= p;
+        }
+        ModInt { value: value as u32 }
+    }
+}
+
+impl Sub for ModInt {
+    type Output = ModInt;
+
+    fn sub(self, other: ModInt) -> ModInt {
+        let p = modulus() as u64;
+        let mut value = self.value as u64 + p - other.value as u64;
+        if value >= p {
+            value -= p;
+        }
+        ModInt { value: value as u32 }
+    }
+}
+
+impl Mul for ModInt {
+    type Output = ModInt;
+
+    fn mul(self, other: ModInt) -> ModInt {
+        let p = modulus();
+        ModInt { value: (self.value as u64 * other.value as u64 % p as u64) as u32 }
+    }
+}
+
+impl Neg for ModInt {
+    type Output = ModInt;
+
+    fn neg(self) -> ModInt {
+        if self.value == 0 {
+            self
+        } else {
+            ModInt { value: modulus() - self.value }
+        }
+    }
+}
+
+impl Display for ModInt {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Coefficient for ModInt {
+    fn zero() -> Self {
+        ModInt::new(0)
+    }
+
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    // `F_p` has no canonical notion of sign, so every representative is
+    // displayed as a plain non-negative value in `0..p`.
+    fn is_negative(&self) -> bool {
+        false
+    }
+
+    fn from_int(n: i32) -> Self {
+        if n < 0 {
+            -ModInt::new((-n) as u32)
+        } else {
+            ModInt::new(n as u32)
+        }
+    }
+}
+
+impl InvertibleCoefficient for ModInt {
+    // By Fermat's little theorem, `a^(p - 1) = 1` for nonzero `a` in `F_p`
+    // (assuming `p` is prime), so `a^(p - 2)` is the inverse of `a`.
+    fn inverse(&self) -> Self {
+        self.pow(modulus() - 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_sub_wrap_around_the_modulus() {
+        set_modulus(7);
+        assert_eq!(ModInt::new(5) + ModInt::new(4), ModInt::new(2));
+        assert_eq!(ModInt::new(2) - ModInt::new(5), ModInt::new(4));
+    }
+
+    #[test]
+    fn add_and_sub_do_not_overflow_near_a_large_modulus() {
+        set_modulus(4_000_000_000);
+        assert_eq!(ModInt::new(3_999_999_999) + ModInt::new(3_999_999_998),
+                   ModInt::new(3_999_999_997));
+        assert_eq!(ModInt::new(1) - ModInt::new(2), ModInt::new(3_999_999_999));
+    }
+
+    #[test]
+    fn neg_of_zero_is_zero() {
+        set_modulus(7);
+        assert_eq!(-ModInt::new(0), ModInt::new(0));
+        assert_eq!(-ModInt::new(3), ModInt::new(4));
+    }
+
+    #[test]
+    fn inverse_is_a_multiplicative_inverse() {
+        set_modulus(101);
+        for value in 1..101 {
+            let a = ModInt::new(value);
+            assert_eq!(a.clone() * a.inverse(), ModInt::new(1));
+        }
+    }
+}
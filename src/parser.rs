@@ -0,0 +1,228 @@
+//! A recursive-descent parser for the human-readable polynomial syntax, e.g.
+//! `3 x_1^2 x_3 - x_2 + 5`.
+
+use std::mem;
+use std::str::Chars;
+use std::iter::Peekable;
+
+use coefficient::Coefficient;
+use errors::*;
+use polynomial::{OddMonomial, OddPolynomial};
+
+#[derive(Clone,Debug,PartialEq)]
+enum Token {
+    Int(u32),
+    Ident,
+    Underscore,
+    Caret,
+    Plus,
+    Minus,
+    Eof,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Lexer { chars: input.chars().peekable() }
+    }
+
+    fn next_token(&mut self) -> Result<Token> {
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        match self.chars.peek().cloned() {
+            None => Ok(Token::Eof),
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_ascii_digit() {
+                        digits.push(c);
+                        self.chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                digits
+                    .parse()
+                    .map(Token::Int)
+                    .chain_err(|| ErrorKind::ParsePolynomial(format!("invalid integer '{}'", digits)))
+            }
+            Some('x') => {
+                self.chars.next();
+                Ok(Token::Ident)
+            }
+            Some('_') => {
+                self.chars.next();
+                Ok(Token::Underscore)
+            }
+            Some('^') => {
+                self.chars.next();
+                Ok(Token::Caret)
+            }
+            Some('+') => {
+                self.chars.next();
+                Ok(Token::Plus)
+            }
+            Some('-') => {
+                self.chars.next();
+                Ok(Token::Minus)
+            }
+            Some(c) => {
+                bail!(ErrorKind::ParsePolynomial(format!("unexpected character '{}'", c)))
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    lexer: Lexer<'a>,
+    current: Token,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self> {
+        let mut lexer = Lexer::new(input);
+        let current = lexer.next_token()?;
+        Ok(Parser { lexer, current })
+    }
+
+    fn advance(&mut self) -> Result<Token> {
+        let next = self.lexer.next_token()?;
+        Ok(mem::replace(&mut self.current, next))
+    }
+
+    fn expect_int(&mut self) -> Result<u32> {
+        match self.advance()? {
+            Token::Int(n) => Ok(n),
+            t => bail!(ErrorKind::ParsePolynomial(format!("expected a number, found {:?}", t))),
+        }
+    }
+
+    fn expect_underscore(&mut self) -> Result<()> {
+        match self.advance()? {
+            Token::Underscore => Ok(()),
+            t => {
+                bail!(ErrorKind::ParsePolynomial(format!("expected '_', found {:?}", t)))
+            }
+        }
+    }
+
+    /// `x_<n>` with an optional `^<power>`, defaulting to a power of 1.
+    fn parse_var_power(&mut self) -> Result<(usize, u32)> {
+        self.advance()?; // consume the `x`
+        self.expect_underscore()?;
+        let n = self.expect_int()?;
+        if n < 1 {
+            bail!(ErrorKind::ParsePolynomial("variable subscripts start at 1".into()));
+        }
+
+        let power = if self.current == Token::Caret {
+            self.advance()?;
+            self.expect_int()?
+        } else {
+            1
+        };
+
+        Ok((n as usize - 1, power))
+    }
+
+    /// A (possibly signed) term: an optional integer coefficient followed by
+    /// zero or more implicitly-multiplied `x_<n>^<power>` factors. Repeated
+    /// variables are combined by adding their exponents.
+    fn parse_term<C: Coefficient>(&mut self, negate: bool) -> Result<OddMonomial<C>> {
+        let mut magnitude = 1i32;
+        let mut has_coefficient = false;
+        if let Token::Int(n) = self.current {
+            if n > i32::MAX as u32 {
+                bail!(ErrorKind::ParsePolynomial(format!("integer literal '{}' is too large", n)));
+            }
+            magnitude = n as i32;
+            has_coefficient = true;
+            self.advance()?;
+        }
+
+        let mut powers = Vec::new();
+        let mut has_factor = false;
+        while self.current == Token::Ident {
+            has_factor = true;
+            let (index, power) = self.parse_var_power()?;
+            while powers.len() <= index {
+                powers.push(0);
+            }
+            powers[index] += power;
+        }
+
+        if !has_coefficient && !has_factor {
+            bail!(ErrorKind::ParsePolynomial(format!("expected a term, found {:?}", self.current)));
+        }
+
+        let coefficient = C::from_int(if negate { -magnitude } else { magnitude });
+        Ok(OddMonomial::new(coefficient, powers))
+    }
+
+    fn parse_polynomial<C: Coefficient>(&mut self) -> Result<OddPolynomial<C>> {
+        let mut negate = match self.current {
+            Token::Minus => {
+                self.advance()?;
+                true
+            }
+            Token::Plus => {
+                self.advance()?;
+                false
+            }
+            _ => false,
+        };
+
+        let mut poly = OddPolynomial::new();
+        loop {
+            let term = self.parse_term(negate)?;
+            poly = &poly + &OddPolynomial::from_monomial(term);
+
+            negate = match self.current {
+                Token::Plus => {
+                    self.advance()?;
+                    false
+                }
+                Token::Minus => {
+                    self.advance()?;
+                    true
+                }
+                Token::Eof => break,
+                ref t => {
+                    bail!(ErrorKind::ParsePolynomial(format!("expected '+' or '-', found {:?}", t)))
+                }
+            };
+        }
+
+        Ok(poly)
+    }
+}
+
+/// Parses a polynomial written in the infix syntax, e.g. `3 x_1^2 x_3 - x_2 + 5`.
+pub fn parse<C: Coefficient>(input: &str) -> Result<OddPolynomial<C>> {
+    Parser::new(input)?.parse_polynomial()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combines_repeated_variables() {
+        let poly = parse::<i32>("x_1 x_1").unwrap();
+        assert_eq!(poly, OddPolynomial::from_monomial(OddMonomial::new(1, vec![2])));
+    }
+
+    #[test]
+    fn rejects_a_coefficient_that_overflows_i32() {
+        assert!(parse::<i32>("3000000000").is_err());
+    }
+}
@@ -1,21 +1,22 @@
-use std::cmp;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::ops::{Add, Mul};
 use std::str::FromStr;
 
 use errors::*;
+use coefficient::{Coefficient, InvertibleCoefficient};
 
 /// An odd monomial.
-#[derive(Clone,Debug,Hash)]
-pub struct OddMonomial {
+#[derive(Clone,Debug,Hash,PartialEq,Eq)]
+pub struct OddMonomial<C: Coefficient> {
     /// The coefficient of the monomial.
-    coefficient: i32,
+    coefficient: C,
     /// The powers of each variable, in ascending order.
     powers: Vec<u32>,
 }
 
-impl OddMonomial {
-    pub fn new(coefficient: i32, powers: Vec<u32>) -> OddMonomial {
+impl<C: Coefficient> OddMonomial<C> {
+    pub fn new(coefficient: C, powers: Vec<u32>) -> OddMonomial<C> {
         OddMonomial {
             coefficient,
             powers,
@@ -23,7 +24,7 @@ impl OddMonomial {
     }
 
     /// Return a single variable
-    pub fn x(n: u32) -> OddMonomial {
+    pub fn x(n: u32) -> OddMonomial<C> {
         let mut powers = Vec::new();
         for _ in 0..n - 1 {
             powers.push(0);
@@ -31,19 +32,32 @@ impl OddMonomial {
         powers.push(1);
 
         OddMonomial {
-            coefficient: 1,
+            coefficient: C::one(),
             powers,
         }
     }
 
     pub fn is_zero(&self) -> bool {
-        self.coefficient == 0
+        self.coefficient.is_zero()
+    }
+
+    /// The staircase monomial `x_1^{n-1} x_2^{n-2} ... x_{n-1}^1`, the
+    /// leading term of the Schubert polynomial for the longest permutation
+    /// in `S_n`.
+    pub fn deltad(n: u32) -> OddMonomial<C> {
+        let powers = (1..n).rev().collect();
+        OddMonomial::new(C::one(), powers)
+    }
+
+    /// The total degree of this monomial, i.e. the sum of its powers.
+    pub fn degree(&self) -> u32 {
+        self.powers.iter().sum()
     }
 
     /// `\sso_n`
     pub fn ss(&self, n: u32) -> Self {
         let n = n as usize;
-        let mut coefficient = self.coefficient;
+        let mut coefficient = self.coefficient.clone();
         let mut powers = self.powers.clone();
         for _ in powers.len()..n + 1 {
             powers.push(0);
@@ -51,7 +65,7 @@ impl OddMonomial {
 
         // Update coefficient
         if (powers[n - 1] + powers[n]) % 2 != 0 {
-            coefficient *= -1;
+            coefficient = -coefficient;
         }
         // Swap variables
         powers.swap(n - 1, n);
@@ -62,14 +76,14 @@ impl OddMonomial {
     /// `\sbo_n`
     pub fn sb(&self, n: u32) -> Self {
         let n = n as usize;
-        let mut coefficient = self.coefficient;
+        let mut coefficient = self.coefficient.clone();
         let mut powers = self.powers.clone();
         for _ in powers.len()..n {
             powers.push(0);
         }
 
         if powers[n - 1] % 2 != 0 {
-            coefficient *= -1;
+            coefficient = -coefficient;
         }
 
         OddMonomial::new(coefficient, powers)
@@ -86,11 +100,11 @@ impl OddMonomial {
         // Swap variables
         powers.swap(n - 2, n - 1);
 
-        OddMonomial::new(self.coefficient, powers)
+        OddMonomial::new(self.coefficient.clone(), powers)
     }
 
     /// `\pso_n`
-    pub fn ps(&self, n: u32) -> OddPolynomial {
+    pub fn ps(&self, n: u32) -> OddPolynomial<C> {
         // Find first nonzero power to apply Leibniz rule
         let mut g = self.clone();
         let pos = match self.powers.iter().position(|&p| p != 0) {
@@ -99,16 +113,18 @@ impl OddMonomial {
         };
         g.powers[pos as usize] -= 1;
 
-        let ps_pos = if pos == n || pos == n - 1 { 1 } else { 0 };
-        let mut first_term = g.clone();
-        first_term.coefficient *= ps_pos;
+        let first_term = if pos == n || pos == n - 1 {
+            OddPolynomial::from_monomial(g.clone())
+        } else {
+            OddPolynomial::new()
+        };
 
-        &OddPolynomial::from_monomial(first_term) +
+        &first_term +
         &(&OddPolynomial::from_monomial(OddMonomial::x(pos + 1).ss(n)) * &g.ps(n))
     }
 
     /// `\pbo_n`
-    pub fn pb(&self, n: u32) -> OddPolynomial {
+    pub fn pb(&self, n: u32) -> OddPolynomial<C> {
         // Find first nonzero power to apply Leibniz rule
         let mut g = self.clone();
         let pos = match self.powers.iter().position(|&p| p != 0) {
@@ -117,16 +133,18 @@ impl OddMonomial {
         };
         g.powers[pos as usize] -= 1;
 
-        let ps_pos = if pos == n - 1 { 1 } else { 0 };
-        let mut first_term = g.clone();
-        first_term.coefficient *= ps_pos;
+        let first_term = if pos == n - 1 {
+            OddPolynomial::from_monomial(g.clone())
+        } else {
+            OddPolynomial::new()
+        };
 
-        &OddPolynomial::from_monomial(first_term) +
+        &first_term +
         &(&OddPolynomial::from_monomial(OddMonomial::x(pos + 1).sb(n)) * &g.pb(n))
     }
 
     /// `\pdo_n`
-    pub fn pd(&self, n: u32) -> OddPolynomial {
+    pub fn pd(&self, n: u32) -> OddPolynomial<C> {
         // Find first nonzero power to apply Leibniz rule
         let mut g = self.clone();
         let pos = match self.powers.iter().position(|&p| p != 0) {
@@ -135,21 +153,23 @@ impl OddMonomial {
         };
         g.powers[pos as usize] -= 1;
 
-        let ps_pos = if pos == n - 2 {
-            1
+        let first_term = if pos == n - 2 {
+            OddPolynomial::from_monomial(g.clone())
         } else if pos == n - 1 {
-            -1
+            let mut negated = g.clone();
+            negated.coefficient = -negated.coefficient;
+            OddPolynomial::from_monomial(negated)
         } else {
-            0
+            OddPolynomial::new()
         };
-        let mut first_term = g.clone();
-        first_term.coefficient *= ps_pos;
 
-        &OddPolynomial::from_monomial(first_term) +
+        &first_term +
         &(&OddPolynomial::from_monomial(OddMonomial::x(pos + 1).sd(n)) * &g.pd(n))
     }
 
-    fn fmt_no_sign(&self, f: &mut Formatter) -> FmtResult {
+    fn fmt_no_sign(&self, f: &mut Formatter) -> FmtResult
+        where C: Display
+    {
         if self.is_zero() {
             return write!(f, "0");
         }
@@ -160,9 +180,12 @@ impl OddMonomial {
             None => return write!(f, "{}", self.coefficient),
         };
 
-        if self.coefficient != 1 && self.coefficient != -1 {
-            if self.coefficient < 0 {
-                write!(f, "{}", -self.coefficient)?;
+        // A coefficient of `-1` is left implicit only when it displays as
+        // negative (the sign is written separately); in `F_p` it is `p - 1`.
+        if self.coefficient != C::one() &&
+           !(self.coefficient.is_negative() && self.coefficient == -C::one()) {
+            if self.coefficient.is_negative() {
+                write!(f, "{}", -self.coefficient.clone())?;
             } else {
                 write!(f, "{}", self.coefficient)?;
             }
@@ -180,23 +203,23 @@ impl OddMonomial {
     }
 }
 
-impl Display for OddMonomial {
+impl<C: Coefficient + Display> Display for OddMonomial<C> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         // Not the most efficient way to do this
-        if self.coefficient < 0 {
+        if self.coefficient.is_negative() {
             write!(f, "-")?;
         }
         self.fmt_no_sign(f)
     }
 }
 
-impl<'a, 'b> Mul<&'a OddMonomial> for &'b OddMonomial {
-    type Output = OddMonomial;
+impl<'a, 'b, C: Coefficient> Mul<&'a OddMonomial<C>> for &'b OddMonomial<C> {
+    type Output = OddMonomial<C>;
 
-    fn mul(self, other: &'a OddMonomial) -> OddMonomial {
+    fn mul(self, other: &'a OddMonomial<C>) -> OddMonomial<C> {
         // We need to multiply the monomials, keeping track of sign changes.
         let mut powers = self.powers.clone();
-        let mut coefficient = self.coefficient * other.coefficient;
+        let mut coefficient = self.coefficient.clone() * other.coefficient.clone();
         // Make sure `powers` is long enough
         for _ in powers.len()..other.powers.len() {
             powers.push(0);
@@ -207,7 +230,7 @@ impl<'a, 'b> Mul<&'a OddMonomial> for &'b OddMonomial {
         for (i, &power) in other.powers.iter().enumerate() {
             powers[i] += power;
             if n_variables % 2 != 0 && power % 2 != 0 {
-                coefficient *= -1;
+                coefficient = -coefficient;
             }
             if n_variables > 0 {
                 n_variables -= powers[i + 1];
@@ -221,30 +244,55 @@ impl<'a, 'b> Mul<&'a OddMonomial> for &'b OddMonomial {
     }
 }
 
+/// Trims trailing zeros from a power vector, so that e.g. `[2, 0]` and `[2]`
+/// are recognized as the same monomial.
+fn canonical_powers(mut powers: Vec<u32>) -> Vec<u32> {
+    while powers.last() == Some(&0) {
+        powers.pop();
+    }
+    powers
+}
+
 /// An odd polynomial.
-#[derive(Clone,Debug,Hash)]
-pub struct OddPolynomial {
-    /// The terms of the polynomial.
-    terms: Vec<OddMonomial>,
+///
+/// Terms are keyed by their canonicalized power vector (trailing zeros
+/// trimmed), so combining like terms is a single map lookup rather than a
+/// linear scan, and iteration order is always the sorted order of the power
+/// vectors.
+#[derive(Clone,Debug,Hash,PartialEq,Eq)]
+pub struct OddPolynomial<C: Coefficient> {
+    /// The terms of the polynomial, keyed by power vector.
+    terms: BTreeMap<Vec<u32>, C>,
 }
 
-impl OddPolynomial {
+impl<C: Coefficient> OddPolynomial<C> {
     pub fn new() -> Self {
-        OddPolynomial { terms: Vec::new() }
+        OddPolynomial { terms: BTreeMap::new() }
     }
 
-    pub fn from_monomial(monomial: OddMonomial) -> Self {
-        if monomial.is_zero() {
-            OddPolynomial::new()
-        } else {
-            OddPolynomial { terms: vec![monomial] }
-        }
+    pub fn from_monomial(monomial: OddMonomial<C>) -> Self {
+        let mut poly = OddPolynomial::new();
+        poly.add_monomial(&monomial);
+        poly
+    }
+
+    fn terms(&self) -> Vec<OddMonomial<C>> {
+        self.terms
+            .iter()
+            .map(|(powers, coefficient)| OddMonomial::new(coefficient.clone(), powers.clone()))
+            .collect()
+    }
+
+    /// Returns the leading term of this polynomial under lexicographic order
+    /// on the power vectors, i.e. the greatest key in `terms`.
+    fn leading_term(&self) -> Option<(&Vec<u32>, &C)> {
+        self.terms.iter().next_back()
     }
 
     /// `\pso_n`
     pub fn ps(&self, n: u32) -> Self {
         let mut res = OddPolynomial::new();
-        for term in &self.terms {
+        for term in self.terms() {
             res = &res + &term.ps(n);
         }
         res
@@ -253,7 +301,7 @@ impl OddPolynomial {
     /// `\pbo_n`
     pub fn pb(&self, n: u32) -> Self {
         let mut res = OddPolynomial::new();
-        for term in &self.terms {
+        for term in self.terms() {
             res = &res + &term.pb(n);
         }
         res
@@ -262,48 +310,39 @@ impl OddPolynomial {
     /// `\pdo_n`
     pub fn pd(&self, n: u32) -> Self {
         let mut res = OddPolynomial::new();
-        for term in &self.terms {
+        for term in self.terms() {
             res = &res + &term.pd(n);
         }
         res
     }
 
-    fn add_monomial(&mut self, other: &OddMonomial) {
+    fn add_monomial(&mut self, other: &OddMonomial<C>) {
         if other.is_zero() {
             return;
         }
 
-        // Try to add to an existing term if possible
-        let pos = self.terms
-            .iter()
-            .position(|term| {
-                for i in 0..cmp::max(term.powers.len(), other.powers.len()) {
-                    if term.powers.get(i).unwrap_or(&0) != other.powers.get(i).unwrap_or(&0) {
-                        return false;
-                    }
-                }
-                true
-            });
-        if let Some(pos) = pos {
-            self.terms[pos].coefficient += other.coefficient;
-            if self.terms[pos].is_zero() {
-                self.terms.remove(pos);
-            }
-        } else {
-            self.terms.push(other.clone());
+        let key = canonical_powers(other.powers.clone());
+        let is_zero = {
+            let entry = self.terms.entry(key.clone()).or_insert_with(C::zero);
+            *entry = entry.clone() + other.coefficient.clone();
+            entry.is_zero()
+        };
+        if is_zero {
+            self.terms.remove(&key);
         }
     }
 }
 
-impl Display for OddPolynomial {
+impl<C: Coefficient + Display> Display for OddPolynomial<C> {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         if self.terms.is_empty() {
             return write!(f, "0");
         }
 
-        write!(f, "{}", self.terms[0])?;
-        for term in self.terms.iter().skip(1) {
-            if term.coefficient < 0 {
+        let mut terms = self.terms().into_iter();
+        write!(f, "{}", terms.next().unwrap())?;
+        for term in terms {
+            if term.coefficient.is_negative() {
                 write!(f, " - ")?;
             } else {
                 write!(f, " + ")?;
@@ -315,10 +354,12 @@ impl Display for OddPolynomial {
     }
 }
 
-impl FromStr for OddPolynomial {
-    type Err = Error;
-
-    fn from_str(input: &str) -> Result<OddPolynomial> {
+impl<C: Coefficient> OddPolynomial<C> {
+    /// Parses the legacy `"coeff power power / coeff power ..."` format
+    /// understood by earlier versions of this crate, kept around so that
+    /// existing scripts using it don't break. New code should prefer the
+    /// infix syntax accepted by `FromStr`.
+    pub fn from_legacy_str(input: &str) -> Result<OddPolynomial<C>> {
         let mut poly = OddPolynomial::new();
 
         for term in input.split('/') {
@@ -338,37 +379,185 @@ impl FromStr for OddPolynomial {
                                            })?);
             }
 
-            poly.add_monomial(&OddMonomial::new(coefficient, powers));
+            poly.add_monomial(&OddMonomial::new(C::from_int(coefficient), powers));
         }
 
         Ok(poly)
     }
 }
 
-impl<'a, 'b> Add<&'a OddPolynomial> for &'b OddPolynomial {
-    type Output = OddPolynomial;
+impl<C: Coefficient> FromStr for OddPolynomial<C> {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<OddPolynomial<C>> {
+        ::parser::parse(input)
+    }
+}
+
+impl<'a, 'b, C: Coefficient> Add<&'a OddPolynomial<C>> for &'b OddPolynomial<C> {
+    type Output = OddPolynomial<C>;
 
-    fn add(self, other: &'a OddPolynomial) -> OddPolynomial {
+    fn add(self, other: &'a OddPolynomial<C>) -> OddPolynomial<C> {
         let mut poly = self.clone();
-        for term in &other.terms {
-            poly.add_monomial(term);
+        for term in other.terms() {
+            poly.add_monomial(&term);
         }
         poly
     }
 }
 
-impl<'a, 'b> Mul<&'a OddPolynomial> for &'b OddPolynomial {
-    type Output = OddPolynomial;
+impl<'a, 'b, C: Coefficient> Mul<&'a OddPolynomial<C>> for &'b OddPolynomial<C> {
+    type Output = OddPolynomial<C>;
 
-    fn mul(self, other: &'a OddPolynomial) -> OddPolynomial {
+    fn mul(self, other: &'a OddPolynomial<C>) -> OddPolynomial<C> {
         let mut poly = OddPolynomial::new();
 
-        for term1 in &self.terms {
-            for term2 in &other.terms {
-                poly.add_monomial(&(term1 * term2));
+        for term1 in self.terms() {
+            for term2 in other.terms() {
+                poly.add_monomial(&(&term1 * &term2));
             }
         }
 
         poly
     }
-}
\ No newline at end of file
+}
+
+/// Whether `divisor` divides `dividend`, i.e. every power in `divisor` is at
+/// most the corresponding power in `dividend` (missing trailing powers are
+/// treated as `0`).
+fn divides(divisor: &[u32], dividend: &[u32]) -> bool {
+    divisor
+        .iter()
+        .enumerate()
+        .all(|(i, &power)| power <= *dividend.get(i).unwrap_or(&0))
+}
+
+/// Componentwise subtraction of `divisor` from `dividend`, assuming
+/// `divides(divisor, dividend)`.
+fn quotient_powers(dividend: &[u32], divisor: &[u32]) -> Vec<u32> {
+    let mut powers = dividend.to_vec();
+    for (i, &power) in divisor.iter().enumerate() {
+        powers[i] -= power;
+    }
+    canonical_powers(powers)
+}
+
+impl<C: InvertibleCoefficient> OddPolynomial<C> {
+    /// Reduces this polynomial to its normal form modulo the ideal generated
+    /// by `generators`, which are assumed to be polynomials in (at most) `n`
+    /// variables.
+    ///
+    /// This performs ordinary multivariate division: fixing lexicographic
+    /// order on the power vectors, the leading term of each generator is
+    /// computed once up front, and then the dividend is repeatedly scanned
+    /// (from its own leading term downwards) for a term divisible by some
+    /// generator's leading term. When one is found, the generator is scaled
+    /// by the matching monomial quotient and subtracted, cancelling that
+    /// term; this is repeated until no term is reducible, at which point the
+    /// remainder is the normal form. An empty generator set leaves `self`
+    /// unchanged.
+    pub fn reduce(&self, generators: &[OddPolynomial<C>], n: u32) -> OddPolynomial<C> {
+        if generators.is_empty() {
+            return self.clone();
+        }
+        debug_assert!(generators
+                          .iter()
+                          .flat_map(|g| g.terms.keys())
+                          .all(|powers| powers.len() as u32 <= n),
+                      "generators must be polynomials in at most n variables");
+
+        // Pair each (nonzero) generator with its own leading term, computed
+        // once up front rather than on every scan of the dividend.
+        let leading: Vec<(&OddPolynomial<C>, Vec<u32>, C)> = generators
+            .iter()
+            .filter_map(|generator| {
+                generator
+                    .leading_term()
+                    .map(|(powers, coefficient)| (generator, powers.clone(), coefficient.clone()))
+            })
+            .collect();
+
+        let mut remainder = self.clone();
+        'reduce: loop {
+            for (powers, coefficient) in remainder.terms.clone().into_iter().rev() {
+                for &(generator, ref lead_powers, ref lead_coefficient) in &leading {
+                    if !divides(lead_powers, &powers) {
+                        continue;
+                    }
+
+                    // `Mul for &OddMonomial` folds in an extra sign flip that
+                    // depends on the shapes of both factors, so the scalar
+                    // that cancels `coefficient` isn't simply
+                    // `coefficient * lead_coefficient.inverse()` negated: we
+                    // multiply a unit quotient monomial against the
+                    // generator's actual leading monomial to see what sign
+                    // that multiplication lands on, then solve for the
+                    // scalar that reproduces `coefficient` exactly.
+                    let quotient = quotient_powers(&powers, lead_powers);
+                    let lead_monomial = OddMonomial::new(lead_coefficient.clone(), lead_powers.clone());
+                    let trial = &OddMonomial::new(C::one(), quotient.clone()) * &lead_monomial;
+                    debug_assert_eq!(trial.powers, powers,
+                                      "quotient monomial must reproduce the divided term's powers");
+
+                    let scale = coefficient * trial.coefficient.inverse();
+                    let negated_quotient = OddMonomial::new(-scale, quotient);
+                    remainder = &remainder +
+                                &(&OddPolynomial::from_monomial(negated_quotient) * generator);
+                    continue 'reduce;
+                }
+            }
+
+            return remainder;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mod_int::{set_modulus, ModInt};
+
+    #[test]
+    fn reduce_with_no_generators_is_identity() {
+        set_modulus(101);
+        let poly = OddPolynomial::from_monomial(OddMonomial::new(ModInt::new(5), vec![1, 2]));
+        assert_eq!(poly.reduce(&[], 2), poly);
+    }
+
+    #[test]
+    fn reduce_cancels_a_multivariate_leading_term() {
+        // Regression test: the quotient `x_3` has to be moved past the odd
+        // power of `x_2` in the generator's leading term `x_2 x_3`, so
+        // `Mul for &OddMonomial` flips the sign of the product. This used to
+        // make the cancellation step compute a quotient with the wrong sign
+        // and loop forever instead of terminating with an empty remainder.
+        set_modulus(101);
+        let dividend = OddPolynomial::from_monomial(OddMonomial::new(ModInt::new(1), vec![0, 1, 2]));
+        let generator = OddPolynomial::from_monomial(OddMonomial::new(ModInt::new(1), vec![0, 1, 1]));
+        let reduced = dividend.reduce(&[generator], 3);
+        assert!(reduced.terms.is_empty());
+    }
+
+    #[test]
+    fn deltad_is_the_staircase_monomial_of_the_right_degree() {
+        let deltad = OddMonomial::<i32>::deltad(4);
+        assert_eq!(deltad, OddMonomial::new(1, vec![3, 2, 1]));
+        assert_eq!(deltad.degree(), 6);
+    }
+
+    #[test]
+    fn terms_with_trailing_zero_powers_are_canonicalized_together() {
+        let mut poly = OddPolynomial::new();
+        poly.add_monomial(&OddMonomial::new(1, vec![2, 0]));
+        poly.add_monomial(&OddMonomial::new(1, vec![2]));
+        assert_eq!(poly, OddPolynomial::from_monomial(OddMonomial::new(2, vec![2])));
+    }
+
+    #[test]
+    fn canceling_terms_are_removed_from_the_map() {
+        let mut poly = OddPolynomial::new();
+        poly.add_monomial(&OddMonomial::new(3, vec![1]));
+        poly.add_monomial(&OddMonomial::new(-3, vec![1, 0]));
+        assert_eq!(poly, OddPolynomial::new());
+    }
+}